@@ -0,0 +1,220 @@
+use crate::{Action, EditorMode};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Key lookup table for a single `EditorMode`, keyed by the pressed key and its modifiers.
+pub type KeyTable = HashMap<(KeyCode, KeyModifiers), Action>;
+
+/// Per-mode keybinding tables, looked up by `EditorMode` in the main dispatch loop.
+pub type KeyMap = HashMap<EditorMode, KeyTable>;
+
+/// Builds the keymap seeded with the editor's built-in bindings.
+pub fn default_keymap() -> KeyMap {
+    let mut map = KeyMap::new();
+
+    let mut visual = KeyTable::new();
+    visual.insert(
+        (KeyCode::Esc, KeyModifiers::NONE),
+        Action::ClearShortuctBuffer,
+    );
+    visual.insert(
+        (KeyCode::Char('i'), KeyModifiers::NONE),
+        Action::EnterInsertMode,
+    );
+    visual.insert(
+        (KeyCode::Char('a'), KeyModifiers::NONE),
+        Action::EnterInsertModeNext,
+    );
+    visual.insert(
+        (KeyCode::Char('o'), KeyModifiers::NONE),
+        Action::EnterInsertModeInNewLine,
+    );
+    visual.insert(
+        (KeyCode::Char(':'), KeyModifiers::NONE),
+        Action::EnterCommandMode,
+    );
+    visual.insert(
+        (KeyCode::Char('h'), KeyModifiers::NONE),
+        Action::MoveCursorLeft,
+    );
+    visual.insert(
+        (KeyCode::Char('l'), KeyModifiers::NONE),
+        Action::MoveCursorRight,
+    );
+    visual.insert(
+        (KeyCode::Char('j'), KeyModifiers::NONE),
+        Action::MoveCursorDown,
+    );
+    visual.insert(
+        (KeyCode::Char('k'), KeyModifiers::NONE),
+        Action::MoveCursorUp,
+    );
+    visual.insert(
+        (KeyCode::Char('x'), KeyModifiers::NONE),
+        Action::RemoveCursorChar,
+    );
+    visual.insert(
+        (KeyCode::Char('w'), KeyModifiers::NONE),
+        Action::MoveWordForward,
+    );
+    visual.insert(
+        (KeyCode::Char('b'), KeyModifiers::NONE),
+        Action::MoveWordBackward,
+    );
+    visual.insert(
+        (KeyCode::Char('e'), KeyModifiers::NONE),
+        Action::MoveWordEnd,
+    );
+    visual.insert(
+        (KeyCode::Char('W'), KeyModifiers::NONE),
+        Action::MoveBigWordForward,
+    );
+    visual.insert(
+        (KeyCode::Char('B'), KeyModifiers::NONE),
+        Action::MoveBigWordBackward,
+    );
+    visual.insert(
+        (KeyCode::Char('E'), KeyModifiers::NONE),
+        Action::MoveBigWordEnd,
+    );
+    visual.insert((KeyCode::Char('u'), KeyModifiers::NONE), Action::Undo);
+    visual.insert((KeyCode::Char('r'), KeyModifiers::CONTROL), Action::Redo);
+    map.insert(EditorMode::Visual, visual);
+
+    let mut insert = KeyTable::new();
+    insert.insert((KeyCode::Esc, KeyModifiers::NONE), Action::EnterVisualMode);
+    insert.insert((KeyCode::Enter, KeyModifiers::NONE), Action::NewLine);
+    insert.insert(
+        (KeyCode::Backspace, KeyModifiers::NONE),
+        Action::BackspaceInInsertMode,
+    );
+    map.insert(EditorMode::Insert, insert);
+
+    let mut command = KeyTable::new();
+    command.insert((KeyCode::Esc, KeyModifiers::NONE), Action::EnterVisualMode);
+    command.insert((KeyCode::Enter, KeyModifiers::NONE), Action::ExecuteCommand);
+    map.insert(EditorMode::Command, command);
+
+    map
+}
+
+/// Path to the user's keymap override, `$XDG_CONFIG_HOME/redit/keymap.ini`
+/// (falling back to `$HOME/.config`).
+fn config_path() -> Option<PathBuf> {
+    let config_home = std::env::var("XDG_CONFIG_HOME").ok().or_else(|| {
+        std::env::var("HOME")
+            .ok()
+            .map(|home| format!("{home}/.config"))
+    })?;
+
+    let mut path = PathBuf::from(config_home);
+    path.push("redit");
+    path.push("keymap.ini");
+    Some(path)
+}
+
+/// Overrides `map` in place with bindings read from the user's keymap config file, if any.
+///
+/// The format is a small INI dialect: `[visual]`/`[insert]`/`[command]` section headers
+/// select the mode, and `"key" = "action_name"` lines bind a key to one of the stable
+/// action names recognized by `Action::from_name`. A key may carry a `ctrl+` prefix (e.g.
+/// `"ctrl+r"`) to bind a control-modified key. Missing file, unknown section, or unknown
+/// key/action names are ignored rather than treated as an error.
+pub fn load(map: &mut KeyMap) {
+    let Some(path) = config_path() else {
+        return;
+    };
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    let mut mode = Some(EditorMode::Visual);
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            mode = match section {
+                "visual" => Some(EditorMode::Visual),
+                "insert" => Some(EditorMode::Insert),
+                "command" => Some(EditorMode::Command),
+                _ => None,
+            };
+            continue;
+        }
+
+        let Some((key, action_name)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_matches('"');
+        let action_name = action_name.trim().trim_matches('"');
+
+        let (Some(mode), Some(key), Some(action)) =
+            (mode, parse_key(key), Action::from_name(action_name))
+        else {
+            continue;
+        };
+
+        map.entry(mode).or_default().insert(key, action);
+    }
+}
+
+/// Parses a key spec, optionally prefixed with `ctrl+` (e.g. `"ctrl+r"`).
+fn parse_key(key: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let (modifiers, key) = match key.strip_prefix("ctrl+") {
+        Some(rest) => (KeyModifiers::CONTROL, rest),
+        None => (KeyModifiers::NONE, key),
+    };
+
+    let code = match key {
+        "esc" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "backspace" => KeyCode::Backspace,
+        _ => {
+            let mut chars = key.chars();
+            let c = chars.next()?;
+            chars.next().is_none().then_some(KeyCode::Char(c))?
+        }
+    };
+
+    Some((code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_key_recognizes_named_keys() {
+        assert_eq!(parse_key("esc"), Some((KeyCode::Esc, KeyModifiers::NONE)));
+        assert_eq!(
+            parse_key("enter"),
+            Some((KeyCode::Enter, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("backspace"),
+            Some((KeyCode::Backspace, KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn parse_key_recognizes_plain_and_ctrl_chars() {
+        assert_eq!(
+            parse_key("w"),
+            Some((KeyCode::Char('w'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("ctrl+r"),
+            Some((KeyCode::Char('r'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn parse_key_rejects_empty_and_multi_char_keys() {
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("ab"), None);
+    }
+}
@@ -1,24 +1,35 @@
 use crossterm::{
     cursor::{position, MoveTo},
-    event::{poll, read, Event, KeyCode},
+    event::{poll, read, Event, KeyCode, KeyModifiers},
     execute, queue,
     style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor, Stylize},
     terminal::{size, Clear, ClearType},
 };
+use ropey::Rope;
 use std::error::Error;
 use std::io::Write;
 use std::io::{self};
 
+mod keymap;
 mod screen_state;
 use screen_state::ScreenState;
 
-#[derive(Clone, PartialEq, Eq)]
+const QUIT_TIMES: u8 = 3;
+
+/// Maximum number of snapshots kept on the undo stack before the oldest is dropped.
+const MAX_UNDO_DEPTH: usize = 100;
+
+/// Column width a tab expands to, rounding up to the next multiple.
+const TAB_STOP: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum EditorMode {
     Insert,
     Visual,
     Command,
 }
 
+#[derive(Clone, Copy)]
 struct Cursor {
     x: usize,
     y: usize,
@@ -59,6 +70,7 @@ impl Cursor {
     }
 }
 
+#[derive(Clone, Copy)]
 enum Action {
     EnterInsertMode,
     EnterVisualMode,
@@ -79,6 +91,54 @@ enum Action {
     BackspaceInInsertMode,
     EnterInsertModeInNewLine,
     RemoveCursorChar,
+    MoveWordForward,
+    MoveWordBackward,
+    MoveWordEnd,
+    MoveBigWordForward,
+    MoveBigWordBackward,
+    MoveBigWordEnd,
+    Undo,
+    Redo,
+}
+
+impl Action {
+    /// Looks up one of the remappable, argument-less actions by its stable name,
+    /// as used in keymap config files (see `keymap::load`).
+    fn from_name(name: &str) -> Option<Action> {
+        match name {
+            "enter_insert_mode" => Some(Action::EnterInsertMode),
+            "enter_visual_mode" => Some(Action::EnterVisualMode),
+            "enter_command_mode" => Some(Action::EnterCommandMode),
+            "move_cursor_left" => Some(Action::MoveCursorLeft),
+            "move_cursor_right" => Some(Action::MoveCursorRight),
+            "move_cursor_down" => Some(Action::MoveCursorDown),
+            "move_cursor_up" => Some(Action::MoveCursorUp),
+            "quit" => Some(Action::Quit),
+            "execute_command" => Some(Action::ExecuteCommand),
+            "new_line" => Some(Action::NewLine),
+            "enter_insert_mode_next" => Some(Action::EnterInsertModeNext),
+            "clear_shortcut_buffer" => Some(Action::ClearShortuctBuffer),
+            "backspace_in_insert_mode" => Some(Action::BackspaceInInsertMode),
+            "enter_insert_mode_in_new_line" => Some(Action::EnterInsertModeInNewLine),
+            "remove_cursor_char" => Some(Action::RemoveCursorChar),
+            "move_word_forward" => Some(Action::MoveWordForward),
+            "move_word_backward" => Some(Action::MoveWordBackward),
+            "move_word_end" => Some(Action::MoveWordEnd),
+            "move_big_word_forward" => Some(Action::MoveBigWordForward),
+            "move_big_word_backward" => Some(Action::MoveBigWordBackward),
+            "move_big_word_end" => Some(Action::MoveBigWordEnd),
+            "undo" => Some(Action::Undo),
+            "redo" => Some(Action::Redo),
+            _ => None,
+        }
+    }
+}
+
+/// A point-in-time copy of the buffer and cursor, used to undo/redo an edit.
+#[derive(Clone)]
+struct Snapshot {
+    lines: Rope,
+    cursor: Cursor,
 }
 
 struct Editor {
@@ -88,13 +148,28 @@ struct Editor {
     cursor: Cursor,
     mode: EditorMode,
     quit: bool,
-    lines: Vec<String>,
+    lines: Rope,
     command: String,
     shortcut_buffer: String,
+    filename: Option<String>,
+    dirty: bool,
+    quit_times: u8,
+    message: String,
+    row_offset: usize,
+    col_offset: usize,
+    keymap: keymap::KeyMap,
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
 }
 
 impl Editor {
-    fn new(columns: u16, rows: u16) -> Self {
+    fn new(
+        columns: u16,
+        rows: u16,
+        filename: Option<String>,
+        lines: Rope,
+        keymap: keymap::KeyMap,
+    ) -> Self {
         Self {
             stdout: io::stdout(),
             columns,
@@ -102,13 +177,65 @@ impl Editor {
             cursor: Cursor::new(),
             mode: EditorMode::Visual,
             quit: false,
-            lines: vec![String::new()],
+            lines,
             command: String::new(),
             shortcut_buffer: String::new(),
+            filename,
+            dirty: false,
+            quit_times: QUIT_TIMES,
+            message: String::new(),
+            row_offset: 0,
+            col_offset: 0,
+            keymap,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
         }
     }
 
+    /// Snapshots the current buffer and cursor onto `undo_stack`, clearing `redo_stack`
+    /// since this starts a fresh edit branch. Caps the stack depth, dropping the oldest
+    /// entry once `MAX_UNDO_DEPTH` is exceeded.
+    fn push_undo_snapshot(&mut self) {
+        if self.undo_stack.len() >= MAX_UNDO_DEPTH {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        });
+        self.redo_stack.clear();
+    }
+
+    fn undo(&mut self) {
+        let Some(snapshot) = self.undo_stack.pop() else {
+            self.message = "Already at oldest change".to_string();
+            return;
+        };
+        self.redo_stack.push(Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        });
+        self.lines = snapshot.lines;
+        self.cursor = snapshot.cursor;
+        self.dirty = true;
+    }
+
+    fn redo(&mut self) {
+        let Some(snapshot) = self.redo_stack.pop() else {
+            self.message = "Already at newest change".to_string();
+            return;
+        };
+        self.undo_stack.push(Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        });
+        self.lines = snapshot.lines;
+        self.cursor = snapshot.cursor;
+        self.dirty = true;
+    }
+
     fn generate(&mut self) -> io::Result<()> {
+        self.scroll();
         self.generate_editor_space()?;
         self.status_line()?;
         self.command_line()?;
@@ -116,23 +243,215 @@ impl Editor {
         Ok(())
     }
 
+    fn text_rows(&self) -> usize {
+        self.rows.saturating_sub(2) as usize
+    }
+
+    fn line_count(&self) -> usize {
+        self.lines.len_lines()
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        let line = self.lines.line(row);
+        let len = line.len_chars();
+        if len > 0 && line.char(len - 1) == '\n' {
+            len - 1
+        } else {
+            len
+        }
+    }
+
+    fn gutter_width(&self) -> usize {
+        self.line_count().max(1).ilog10() as usize + 1
+    }
+
+    /// Whether `idx` is the first (and only) char position of an empty line, i.e. a blank
+    /// line that word motions should stop on rather than skip over as plain whitespace.
+    fn is_blank_line_start(&self, idx: usize) -> bool {
+        let row = self.lines.char_to_line(idx);
+        self.line_len(row) == 0 && self.lines.line_to_char(row) == idx
+    }
+
+    /// Screen column that storage index `col` on `row` renders at, expanding tabs up to
+    /// the next multiple of `TAB_STOP`. Edits keep operating on the raw storage index;
+    /// only rendering and position reporting need this screen-relative column.
+    fn render_x(&self, row: usize, col: usize) -> usize {
+        let line = self.lines.line(row);
+        let mut render_x = 0;
+        for c in line.chars().take(col) {
+            if c == '\t' {
+                render_x += TAB_STOP - (render_x % TAB_STOP);
+            } else {
+                render_x += 1;
+            }
+        }
+        render_x
+    }
+
+    fn cursor_char_idx(&self) -> usize {
+        let (c_col, c_row) = self.cursor.get_position();
+        self.lines.line_to_char(c_row) + c_col.min(self.line_len(c_row))
+    }
+
+    fn set_cursor_char_idx(&mut self, idx: usize) {
+        let row = self.lines.char_to_line(idx);
+        self.cursor.y = row;
+        self.cursor.x = idx - self.lines.line_to_char(row);
+    }
+
+    /// Classifies `c` into a word-motion class: `None` for whitespace, `Some(0)` for a
+    /// "word" char (or any non-blank char once `big` collapses word/punctuation together),
+    /// `Some(1)` for punctuation.
+    fn word_class(c: char, big: bool) -> Option<u8> {
+        if c.is_whitespace() {
+            None
+        } else if big || c.is_alphanumeric() || c == '_' {
+            Some(0)
+        } else {
+            Some(1)
+        }
+    }
+
+    /// `w`/`W`: move to the start of the next word.
+    fn move_word_forward(&mut self, big: bool) {
+        let len = self.lines.len_chars();
+        let mut idx = self.cursor_char_idx();
+        if idx >= len {
+            return;
+        }
+
+        let start_class = Self::word_class(self.lines.char(idx), big);
+        while idx < len
+            && start_class.is_some()
+            && Self::word_class(self.lines.char(idx), big) == start_class
+        {
+            idx += 1;
+        }
+        while idx < len && self.lines.char(idx).is_whitespace() {
+            idx += 1;
+            if idx < len && self.is_blank_line_start(idx) {
+                break;
+            }
+        }
+
+        self.set_cursor_char_idx(idx.min(len.saturating_sub(1)));
+    }
+
+    /// `b`/`B`: move to the start of the previous word.
+    fn move_word_backward(&mut self, big: bool) {
+        let mut idx = self.cursor_char_idx();
+        if idx == 0 {
+            return;
+        }
+        idx -= 1;
+
+        while idx > 0 && self.lines.char(idx).is_whitespace() && !self.is_blank_line_start(idx) {
+            idx -= 1;
+        }
+
+        if !self.lines.char(idx).is_whitespace() {
+            let class = Self::word_class(self.lines.char(idx), big);
+            while idx > 0 && Self::word_class(self.lines.char(idx - 1), big) == class {
+                idx -= 1;
+            }
+        }
+
+        self.set_cursor_char_idx(idx);
+    }
+
+    /// `e`/`E`: move to the end of the next word.
+    fn move_word_end(&mut self, big: bool) {
+        let len = self.lines.len_chars();
+        let mut idx = self.cursor_char_idx();
+        if idx + 1 >= len {
+            return;
+        }
+        idx += 1;
+
+        while idx < len && self.lines.char(idx).is_whitespace() {
+            idx += 1;
+        }
+
+        if idx < len {
+            let class = Self::word_class(self.lines.char(idx), big);
+            while idx + 1 < len && Self::word_class(self.lines.char(idx + 1), big) == class {
+                idx += 1;
+            }
+        }
+
+        self.set_cursor_char_idx(idx.min(len.saturating_sub(1)));
+    }
+
+    fn scroll(&mut self) {
+        let (c_col, c_row) = self.cursor.get_position();
+        let render_col = self.render_x(c_row, c_col);
+        let text_rows = self.text_rows();
+
+        if c_row < self.row_offset {
+            self.row_offset = c_row;
+        }
+        if c_row >= self.row_offset + text_rows {
+            self.row_offset = c_row - text_rows + 1;
+        }
+
+        let text_columns = (self.columns as usize).saturating_sub(self.gutter_width() + 1);
+
+        if render_col < self.col_offset {
+            self.col_offset = render_col;
+        }
+        if render_col >= self.col_offset + text_columns {
+            self.col_offset = render_col - text_columns + 1;
+        }
+    }
+
     fn generate_editor_space(&mut self) -> io::Result<()> {
         let (c_col, c_row) = self.cursor.get_position();
+        let cursor_render_col = self.render_x(c_row, c_col);
+        let text_rows = self.text_rows();
+        let gutter_width = self.gutter_width();
 
         let mut is_cursor_drawed = false;
 
-        for (row, line) in self.lines.iter().enumerate() {
-            queue!(self.stdout, MoveTo(0, row as u16))?;
-            for (col, c) in line.chars().enumerate() {
-                if !is_cursor_drawed && (c_col, c_row) == (col, row) {
-                    queue!(self.stdout, SetBackgroundColor(Color::Blue))?;
-                    queue!(self.stdout, SetForegroundColor(Color::Black))?;
-                    queue!(self.stdout, Print(c))?;
-                    is_cursor_drawed = true;
-                    queue!(self.stdout, ResetColor)?;
+        let visible_lines = self
+            .lines
+            .lines()
+            .enumerate()
+            .skip(self.row_offset)
+            .take(text_rows);
+
+        for (screen_row, (row, line)) in visible_lines.enumerate() {
+            queue!(self.stdout, MoveTo(0, screen_row as u16))?;
+            queue!(self.stdout, SetForegroundColor(Color::DarkGrey))?;
+            queue!(
+                self.stdout,
+                Print(format!("{:>width$} ", row + 1, width = gutter_width))
+            )?;
+            queue!(self.stdout, ResetColor)?;
+
+            let mut render_col = 0;
+            for raw_c in line.chars().filter(|&c| c != '\n') {
+                let (display_c, cell_width) = if raw_c == '\t' {
+                    (' ', TAB_STOP - (render_col % TAB_STOP))
                 } else {
-                    queue!(self.stdout, Print(c))?;
+                    (raw_c, 1)
+                };
+
+                for cell in 0..cell_width {
+                    let col = render_col + cell;
+                    if col < self.col_offset {
+                        continue;
+                    }
+                    if !is_cursor_drawed && row == c_row && col == cursor_render_col {
+                        queue!(self.stdout, SetBackgroundColor(Color::Blue))?;
+                        queue!(self.stdout, SetForegroundColor(Color::Black))?;
+                        queue!(self.stdout, Print(display_c))?;
+                        is_cursor_drawed = true;
+                        queue!(self.stdout, ResetColor)?;
+                    } else {
+                        queue!(self.stdout, Print(display_c))?;
+                    }
                 }
+                render_col += cell_width;
             }
 
             if row == c_row && !is_cursor_drawed {
@@ -162,12 +481,13 @@ impl Editor {
         queue!(self.stdout, SetForegroundColor(Color::White))?;
         queue!(self.stdout, MoveTo(self.columns - 20, self.rows - 2))?;
         let (c_col, c_row) = self.cursor.get_position();
+        let render_col = self.render_x(c_row, c_col);
         queue!(
             self.stdout,
             Print(format!(
                 "{line},{column}",
                 line = c_row + 1,
-                column = c_col + 1
+                column = render_col + 1
             ))
         )?;
 
@@ -187,7 +507,11 @@ impl Editor {
         }
 
         if self.mode == EditorMode::Visual {
-            queue!(self.stdout, Print(" ".repeat(self.rows.into())))?;
+            if self.message.is_empty() {
+                queue!(self.stdout, Print(" ".repeat(self.rows.into())))?;
+            } else {
+                queue!(self.stdout, Print(&self.message))?;
+            }
         }
 
         Ok(())
@@ -195,10 +519,51 @@ impl Editor {
 
     fn execute_command(&mut self) {
         match self.command.as_str() {
-            "q" => self.quit = true,
+            "q" => {
+                if self.dirty && self.quit_times > 0 {
+                    self.message = format!(
+                        "Unsaved changes, press :q {} more time{} to quit without saving",
+                        self.quit_times,
+                        if self.quit_times == 1 { "" } else { "s" }
+                    );
+                    self.quit_times -= 1;
+                } else {
+                    self.quit = true;
+                }
+            }
+            "q!" => self.quit = true,
+            "w" => {
+                self.save();
+            }
+            "wq" => {
+                if self.save() {
+                    self.quit = true;
+                }
+            }
             _ => {}
         }
     }
+
+    /// Writes `lines` to `filename`, returning whether the save succeeded.
+    fn save(&mut self) -> bool {
+        match &self.filename {
+            Some(filename) => match std::fs::write(filename, self.lines.to_string()) {
+                Ok(()) => {
+                    self.dirty = false;
+                    self.message = format!("\"{}\" written", filename);
+                    true
+                }
+                Err(e) => {
+                    self.message = format!("Can't save, {}", e);
+                    false
+                }
+            },
+            None => {
+                self.message = "No file name".to_string();
+                false
+            }
+        }
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -206,8 +571,20 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let mut stdout = io::stdout();
 
+    let filename = std::env::args().nth(1);
+    let lines = match &filename {
+        Some(filename) => match std::fs::read_to_string(filename) {
+            Ok(contents) => Rope::from_str(&contents),
+            Err(_) => Rope::new(),
+        },
+        None => Rope::new(),
+    };
+
+    let mut keymap = keymap::default_keymap();
+    keymap::load(&mut keymap);
+
     let (columns, rows) = size()?;
-    let mut editor = Editor::new(columns, rows);
+    let mut editor = Editor::new(columns, rows, filename, lines, keymap);
 
     editor.generate().map_err(|e| {
         eprintln!("Something goes wrong during editor generation: {}", e);
@@ -217,29 +594,35 @@ fn main() -> Result<(), Box<dyn Error>> {
     while !editor.quit {
         if let Ok(event) = read() {
             execute!(stdout, Clear(ClearType::All)).unwrap();
-            let action = match editor.mode {
-                EditorMode::Insert => handle_insert_mode_event(&event),
-                EditorMode::Visual => handle_visual_mode_event(&event),
-                EditorMode::Command => handle_command_mode_event(&event),
+            let action = {
+                let table = &editor.keymap[&editor.mode];
+                match editor.mode {
+                    EditorMode::Insert => handle_insert_mode_event(&event, table),
+                    EditorMode::Visual => handle_visual_mode_event(&event, table),
+                    EditorMode::Command => handle_command_mode_event(&event, table),
+                }
             };
 
             match action {
                 Action::Quit => editor.quit = true,
-                Action::EnterInsertMode => editor.mode = EditorMode::Insert,
+                Action::EnterInsertMode => {
+                    editor.push_undo_snapshot();
+                    editor.mode = EditorMode::Insert;
+                }
                 Action::EnterInsertModeNext => {
+                    editor.push_undo_snapshot();
                     editor.mode = EditorMode::Insert;
                     editor.cursor.move_right(usize::MAX);
                 }
                 Action::EnterInsertModeInNewLine => {
+                    editor.push_undo_snapshot();
                     let (_, c_row) = editor.cursor.get_position();
-                    if c_row == editor.lines.len() - 1 {
-                        editor.lines.push(String::new());
-                    } else {
-                        editor.lines.insert(c_row + 1, String::new());
-                    }
+                    let line_end = editor.lines.line_to_char(c_row) + editor.line_len(c_row);
+                    editor.lines.insert_char(line_end, '\n');
 
                     editor.mode = EditorMode::Insert;
                     editor.cursor.move_down(usize::MAX);
+                    editor.cursor.x = 0;
                 }
                 Action::EnterVisualMode => {
                     if editor.mode == EditorMode::Command {
@@ -248,10 +631,8 @@ fn main() -> Result<(), Box<dyn Error>> {
                     if editor.mode == EditorMode::Insert {
                         let (c_col, c_row) = editor.cursor.get_position();
 
-                        if let Some(line) = editor.lines.get(c_row) {
-                            if line.len() == c_col {
-                                editor.cursor.move_left();
-                            }
+                        if editor.line_len(c_row) == c_col {
+                            editor.cursor.move_left();
                         }
                     }
                     editor.mode = EditorMode::Visual;
@@ -260,42 +641,51 @@ fn main() -> Result<(), Box<dyn Error>> {
                 Action::MoveCursorLeft => editor.cursor.move_left(),
                 Action::MoveCursorRight => {
                     let (_, c_row) = editor.cursor.get_position();
-                    if let Some(line) = editor.lines.get(c_row) {
-                        if line.len() == 0 {
-                            editor.cursor.move_right(0);
-                        } else {
-                            editor.cursor.move_right(line.len() - 1);
-                        }
+                    let len = editor.line_len(c_row);
+                    if len == 0 {
+                        editor.cursor.move_right(0);
+                    } else {
+                        editor.cursor.move_right(len - 1);
                     }
                 }
                 Action::MoveCursorDown => {
-                    let rows = editor.lines.len();
+                    let rows = editor.line_count();
                     if rows == 1 {
                         editor.cursor.move_down(0);
                     }
                     editor.cursor.move_down(rows - 1);
                 }
                 Action::MoveCursorUp => editor.cursor.move_up(),
+                Action::MoveWordForward => editor.move_word_forward(false),
+                Action::MoveWordBackward => editor.move_word_backward(false),
+                Action::MoveWordEnd => editor.move_word_end(false),
+                Action::MoveBigWordForward => editor.move_word_forward(true),
+                Action::MoveBigWordBackward => editor.move_word_backward(true),
+                Action::MoveBigWordEnd => editor.move_word_end(true),
                 Action::EnterCommandChar(c) => editor.command.push(c),
-                Action::ExecuteCommand => editor.execute_command(),
+                Action::ExecuteCommand => {
+                    editor.execute_command();
+                    editor.command.clear();
+                    editor.mode = EditorMode::Visual;
+                }
                 Action::EnterChar(c) => {
                     let (c_col, c_row) = editor.cursor.get_position();
-                    if let Some(line) = editor.lines.get_mut(c_row) {
-                        if line.is_empty() {
-                            line.push(c);
-                            editor.cursor.move_right(usize::MAX)
-                        } else if c_col > line.len() {
-                            line.push(c);
-                            editor.cursor.move_right(usize::MAX)
-                        } else {
-                            line.insert(c_col, c);
-                            editor.cursor.move_right(usize::MAX)
-                        }
-                    }
+                    let char_idx =
+                        editor.lines.line_to_char(c_row) + c_col.min(editor.line_len(c_row));
+                    editor.lines.insert_char(char_idx, c);
+                    editor.cursor.move_right(usize::MAX);
+                    editor.dirty = true;
+                    editor.quit_times = QUIT_TIMES;
                 }
                 Action::NewLine => {
-                    editor.lines.push(String::new());
+                    let (c_col, c_row) = editor.cursor.get_position();
+                    let char_idx =
+                        editor.lines.line_to_char(c_row) + c_col.min(editor.line_len(c_row));
+                    editor.lines.insert_char(char_idx, '\n');
                     editor.cursor.move_down(usize::MAX);
+                    editor.cursor.x = 0;
+                    editor.dirty = true;
+                    editor.quit_times = QUIT_TIMES;
                 }
                 Action::AppendShortcutChar(c) => {
                     editor.shortcut_buffer.push(c);
@@ -311,8 +701,17 @@ fn main() -> Result<(), Box<dyn Error>> {
                         "dd" => {
                             let (_, c_row) = editor.cursor.get_position();
 
-                            if editor.lines.len() > 1 {
-                                editor.lines.remove(c_row as usize);
+                            if editor.line_count() > 1 {
+                                editor.push_undo_snapshot();
+                                let start = editor.lines.line_to_char(c_row);
+                                let end = if c_row + 1 < editor.line_count() {
+                                    editor.lines.line_to_char(c_row + 1)
+                                } else {
+                                    editor.lines.len_chars()
+                                };
+                                editor.lines.remove(start..end);
+                                editor.dirty = true;
+                                editor.quit_times = QUIT_TIMES;
                             }
                             editor.shortcut_buffer.clear();
                         }
@@ -323,34 +722,39 @@ fn main() -> Result<(), Box<dyn Error>> {
                     let (c_col, c_row) = editor.cursor.get_position();
 
                     if c_col == 0 && c_row != 0 {
-                        if let Some(line) = editor.lines.get_mut(c_row) {
-                            if line.is_empty() {
-                                editor.lines.remove(c_row);
-                            } else {
-                                // move line content to line above
-                            }
-                        };
+                        let prev_len = editor.line_len(c_row - 1);
+                        let merge_at = editor.lines.line_to_char(c_row - 1) + prev_len;
+                        editor.lines.remove(merge_at..merge_at + 1);
+                        editor.cursor.move_up();
+                        editor.cursor.x = prev_len;
+                        editor.dirty = true;
+                        editor.quit_times = QUIT_TIMES;
                     } else if c_col > 0 {
-                        let char_index_to_remove = c_col - 1;
-                        if let Some(line) = editor.lines.get_mut(c_row) {
-                            line.remove(char_index_to_remove);
-                            editor.cursor.move_left();
-                        }
+                        let char_idx = editor.lines.line_to_char(c_row) + c_col - 1;
+                        editor.lines.remove(char_idx..char_idx + 1);
+                        editor.cursor.move_left();
+                        editor.dirty = true;
+                        editor.quit_times = QUIT_TIMES;
                     }
                 }
                 Action::ClearShortuctBuffer => editor.shortcut_buffer.clear(),
                 Action::RemoveCursorChar => {
                     let (c_col, c_row) = editor.cursor.get_position();
+                    let len = editor.line_len(c_row);
 
-                    if let Some(line) = editor.lines.get_mut(c_row) {
-                        if !line.is_empty() {
-                            line.remove(c_col);
-                            if c_col == line.len() {
-                                editor.cursor.move_left();
-                            }
+                    if len > 0 && c_col < len {
+                        editor.push_undo_snapshot();
+                        let char_idx = editor.lines.line_to_char(c_row) + c_col;
+                        editor.lines.remove(char_idx..char_idx + 1);
+                        if c_col == len - 1 {
+                            editor.cursor.move_left();
                         }
+                        editor.dirty = true;
+                        editor.quit_times = QUIT_TIMES;
                     }
                 }
+                Action::Undo => editor.undo(),
+                Action::Redo => editor.redo(),
                 _ => {}
             }
         }
@@ -366,47 +770,167 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn handle_insert_mode_event(event: &Event) -> Action {
+/// Looks up `key_event` in `table`. Shift is masked out of the lookup modifiers since a
+/// `Char`'s case already carries it; only the remaining modifiers (e.g. `CONTROL`) matter.
+fn lookup<'a>(
+    key_event: &crossterm::event::KeyEvent,
+    table: &'a keymap::KeyTable,
+) -> Option<&'a Action> {
+    table.get(&(key_event.code, key_event.modifiers - KeyModifiers::SHIFT))
+}
+
+fn handle_insert_mode_event(event: &Event, table: &keymap::KeyTable) -> Action {
     match event {
-        Event::Key(key_event) => match key_event.code {
-            KeyCode::Esc => Action::EnterVisualMode,
-            KeyCode::Enter => Action::NewLine,
-            KeyCode::Backspace => Action::BackspaceInInsertMode,
-            KeyCode::Char(c) => Action::EnterChar(c),
-            _ => Action::Unknown,
+        Event::Key(key_event) => match lookup(key_event, table) {
+            Some(action) => *action,
+            None => match key_event.code {
+                KeyCode::Char(c) => Action::EnterChar(c),
+                _ => Action::Unknown,
+            },
         },
         _ => Action::Unknown,
     }
 }
 
-fn handle_visual_mode_event(event: &Event) -> Action {
+fn handle_visual_mode_event(event: &Event, table: &keymap::KeyTable) -> Action {
     match event {
-        Event::Key(key_event) => match key_event.code {
-            KeyCode::Esc => Action::ClearShortuctBuffer,
-            KeyCode::Char('i') => Action::EnterInsertMode,
-            KeyCode::Char('a') => Action::EnterInsertModeNext,
-            KeyCode::Char('o') => Action::EnterInsertModeInNewLine,
-            KeyCode::Char(':') => Action::EnterCommandMode,
-            KeyCode::Char('h') => Action::MoveCursorLeft,
-            KeyCode::Char('l') => Action::MoveCursorRight,
-            KeyCode::Char('j') => Action::MoveCursorDown,
-            KeyCode::Char('k') => Action::MoveCursorUp,
-            KeyCode::Char('x') => Action::RemoveCursorChar,
-            KeyCode::Char(c) => Action::AppendShortcutChar(c),
-            _ => Action::Unknown,
+        Event::Key(key_event) => match lookup(key_event, table) {
+            Some(action) => *action,
+            None => match key_event.code {
+                KeyCode::Char(c) => Action::AppendShortcutChar(c),
+                _ => Action::Unknown,
+            },
         },
         _ => Action::Unknown,
     }
 }
 
-fn handle_command_mode_event(event: &Event) -> Action {
+fn handle_command_mode_event(event: &Event, table: &keymap::KeyTable) -> Action {
     match event {
-        Event::Key(key_event) => match key_event.code {
-            KeyCode::Esc => Action::EnterVisualMode,
-            KeyCode::Enter => Action::ExecuteCommand,
-            KeyCode::Char(c) => Action::EnterCommandChar(c),
-            _ => Action::Unknown,
+        Event::Key(key_event) => match lookup(key_event, table) {
+            Some(action) => *action,
+            None => match key_event.code {
+                KeyCode::Char(c) => Action::EnterCommandChar(c),
+                _ => Action::Unknown,
+            },
         },
         _ => Action::Unknown,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor_with(text: &str) -> Editor {
+        Editor::new(80, 24, None, Rope::from_str(text), keymap::default_keymap())
+    }
+
+    #[test]
+    fn action_from_name_resolves_known_and_rejects_unknown() {
+        assert!(matches!(Action::from_name("quit"), Some(Action::Quit)));
+        assert!(matches!(
+            Action::from_name("move_word_forward"),
+            Some(Action::MoveWordForward)
+        ));
+        assert!(Action::from_name("not_a_real_action").is_none());
+    }
+
+    #[test]
+    fn word_forward_stops_on_single_blank_line() {
+        let mut editor = editor_with("foo\n\nbar");
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 1));
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 2));
+    }
+
+    #[test]
+    fn word_forward_stops_on_each_of_several_blank_lines() {
+        let mut editor = editor_with("foo\n\n\nbar");
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 1));
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 2));
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 3));
+    }
+
+    #[test]
+    fn word_forward_clamps_at_buffer_end() {
+        let mut editor = editor_with("foo");
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (2, 0));
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (2, 0));
+    }
+
+    #[test]
+    fn word_backward_stops_on_each_blank_line() {
+        let mut editor = editor_with("foo\n\n\nbar");
+        editor.cursor.y = 3;
+        editor.cursor.x = 0;
+
+        editor.move_word_backward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 2));
+
+        editor.move_word_backward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 1));
+
+        editor.move_word_backward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 0));
+    }
+
+    #[test]
+    fn word_backward_clamps_at_buffer_start() {
+        let mut editor = editor_with("foo");
+        editor.cursor.x = 2;
+
+        editor.move_word_backward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 0));
+
+        editor.move_word_backward(false);
+        assert_eq!(editor.cursor.get_position(), (0, 0));
+    }
+
+    #[test]
+    fn word_end_skips_blank_lines_to_next_word_end() {
+        let mut editor = editor_with("foo\n\nbar");
+
+        editor.move_word_end(false);
+        assert_eq!(editor.cursor.get_position(), (2, 0));
+
+        editor.move_word_end(false);
+        assert_eq!(editor.cursor.get_position(), (2, 2));
+    }
+
+    #[test]
+    fn word_end_clamps_at_buffer_end() {
+        let mut editor = editor_with("foo");
+
+        editor.move_word_end(false);
+        assert_eq!(editor.cursor.get_position(), (2, 0));
+
+        editor.move_word_end(false);
+        assert_eq!(editor.cursor.get_position(), (2, 0));
+    }
+
+    #[test]
+    fn big_word_collapses_word_and_punctuation_classes() {
+        let mut editor = editor_with("foo.bar baz");
+
+        editor.move_word_forward(false);
+        assert_eq!(editor.cursor.get_position(), (3, 0));
+
+        editor.cursor.x = 0;
+        editor.move_word_forward(true);
+        assert_eq!(editor.cursor.get_position(), (8, 0));
+    }
+}